@@ -0,0 +1,33 @@
+//! SPL Associated Token Account (ATA) helpers.
+//!
+//! Wraps the standard ATA-program PDA derivation and its `create` instruction so callers
+//! don't have to already know a wallet's token-account address - the most common source of
+//! invalid transfers in `/send/token` before this existed.
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account};
+
+/// Derives the Associated Token Account address for `(owner, mint)`.
+pub fn derive_associated_token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, mint)
+}
+
+/// Builds the instruction that creates `owner`'s ATA for `mint`, paid for by `payer`.
+pub fn create_associated_token_account_instruction(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    create_associated_token_account(payer, owner, mint, &spl_token::id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn derives_known_ata_vector() {
+        let owner = Pubkey::from_str("4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T").unwrap();
+        let mint = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+        let expected = Pubkey::from_str("F8biqkCRK2tHR6EncrcXDGgVTkGRrtojqyW39w41Qspn").unwrap();
+
+        assert_eq!(derive_associated_token_account(&owner, &mint), expected);
+    }
+}
@@ -0,0 +1,207 @@
+//! On-disk keystore for the remote-signer endpoints.
+//!
+//! Keypairs are loaded from a directory at startup, one file per key, named by the
+//! key's base58 pubkey. Callers reference a key by pubkey; the raw secret never
+//! appears in a request or response again. Files may optionally be encrypted at
+//! rest with a passphrase-derived key (see [`Keystore::unlock`]).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base58::{FromBase58, ToBase58};
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha256;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use tokio::sync::RwLock;
+
+const PBKDF2_ROUNDS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug)]
+pub struct KeystoreError(pub String);
+
+impl std::fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for KeystoreError {}
+
+/// In-memory cache of keypairs, backed by `dir` on disk.
+pub struct Keystore {
+    dir: PathBuf,
+    passphrase: Option<String>,
+    keys: RwLock<HashMap<String, Arc<Keypair>>>,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    key
+}
+
+impl Keystore {
+    /// Loads every key file under `dir`, decrypting with `passphrase` if set. Directory is
+    /// created if it doesn't exist yet so a fresh deployment can `POST /keys/import` into it.
+    pub async fn load(dir: impl Into<PathBuf>, passphrase: Option<String>) -> Result<Self, KeystoreError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| KeystoreError(format!("Failed to create keystore dir: {e}")))?;
+
+        let mut keys = HashMap::new();
+        for entry in fs::read_dir(&dir).map_err(|e| KeystoreError(format!("Failed to read keystore dir: {e}")))? {
+            let entry = entry.map_err(|e| KeystoreError(format!("Failed to read keystore entry: {e}")))?;
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let raw = fs::read(&path).map_err(|e| KeystoreError(format!("Failed to read key file: {e}")))?;
+            let secret_bytes = decode_key_file(&raw, passphrase.as_deref())?;
+            let keypair = Keypair::try_from(secret_bytes.as_slice())
+                .map_err(|e| KeystoreError(format!("Corrupt key file {}: {e}", path.display())))?;
+            keys.insert(keypair.pubkey().to_string(), Arc::new(keypair));
+        }
+
+        Ok(Keystore { dir, passphrase, keys: RwLock::new(keys) })
+    }
+
+    /// Imports a base58 secret key, persisting it (encrypted, if a passphrase is configured)
+    /// under `<dir>/<pubkey>`, and returns the pubkey it was stored under.
+    pub async fn import(&self, secret_base58: &str) -> Result<String, KeystoreError> {
+        let secret_bytes = secret_base58
+            .from_base58()
+            .map_err(|_| KeystoreError("Invalid base58 secret key".to_string()))?;
+        let keypair =
+            Keypair::try_from(secret_bytes.as_slice()).map_err(|_| KeystoreError("Invalid secret key".to_string()))?;
+        let pubkey = keypair.pubkey().to_string();
+
+        let encoded = encode_key_file(&secret_bytes, self.passphrase.as_deref());
+        let path = self.key_path(&pubkey);
+        fs::write(&path, encoded).map_err(|e| KeystoreError(format!("Failed to write key file: {e}")))?;
+
+        self.keys.write().await.insert(pubkey.clone(), Arc::new(keypair));
+        Ok(pubkey)
+    }
+
+    /// Lists the pubkeys of every loaded key. Never returns secret material.
+    pub async fn list_pubkeys(&self) -> Vec<String> {
+        self.keys.read().await.keys().cloned().collect()
+    }
+
+    /// Signs `message` with the key identified by `pubkey`, if it is loaded.
+    pub async fn sign(&self, pubkey: &str, message: &[u8]) -> Option<Signature> {
+        let keys = self.keys.read().await;
+        keys.get(pubkey).map(|kp| kp.sign_message(message))
+    }
+
+    fn key_path(&self, pubkey: &str) -> PathBuf {
+        Path::new(&self.dir).join(pubkey)
+    }
+}
+
+/// Plaintext files start with this marker so `decode_key_file` can tell them apart from
+/// encrypted ones without needing a separate extension or manifest.
+const PLAINTEXT_MARKER: &[u8] = b"plain:";
+const ENCRYPTED_MARKER: &[u8] = b"enc1:";
+
+fn encode_key_file(secret_bytes: &[u8], passphrase: Option<&str>) -> Vec<u8> {
+    match passphrase {
+        None => [PLAINTEXT_MARKER, secret_bytes.to_base58().as_bytes()].concat(),
+        Some(passphrase) => {
+            let mut salt = [0u8; SALT_LEN];
+            use aes_gcm::aead::rand_core::RngCore;
+            OsRng.fill_bytes(&mut salt);
+            let key = derive_key(passphrase, &salt);
+            let cipher = Aes256Gcm::new_from_slice(&key).expect("key is 32 bytes");
+
+            let mut nonce_bytes = [0u8; NONCE_LEN];
+            OsRng.fill_bytes(&mut nonce_bytes);
+            let nonce = Nonce::from_slice(&nonce_bytes);
+
+            let ciphertext = cipher.encrypt(nonce, secret_bytes).expect("encryption cannot fail here");
+
+            let mut out = ENCRYPTED_MARKER.to_vec();
+            out.extend_from_slice(&salt);
+            out.extend_from_slice(&nonce_bytes);
+            out.extend_from_slice(&ciphertext);
+            out
+        }
+    }
+}
+
+fn decode_key_file(raw: &[u8], passphrase: Option<&str>) -> Result<Vec<u8>, KeystoreError> {
+    if let Some(rest) = raw.strip_prefix(PLAINTEXT_MARKER) {
+        let text = std::str::from_utf8(rest).map_err(|_| KeystoreError("Corrupt key file".to_string()))?;
+        return text.from_base58().map_err(|_| KeystoreError("Corrupt key file".to_string()));
+    }
+
+    if let Some(rest) = raw.strip_prefix(ENCRYPTED_MARKER) {
+        let passphrase = passphrase.ok_or_else(|| {
+            KeystoreError("Key file is encrypted but no keystore passphrase is configured".to_string())
+        })?;
+        if rest.len() < SALT_LEN + NONCE_LEN {
+            return Err(KeystoreError("Corrupt key file".to_string()));
+        }
+        let (salt, rest) = rest.split_at(SALT_LEN);
+        let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let key = derive_key(passphrase, salt);
+        let cipher = Aes256Gcm::new_from_slice(&key).expect("key is 32 bytes");
+        let nonce = Nonce::from_slice(nonce_bytes);
+
+        return cipher
+            .decrypt(nonce, ciphertext)
+            .map_err(|_| KeystoreError("Failed to decrypt key file (wrong passphrase?)".to_string()));
+    }
+
+    Err(KeystoreError("Unrecognized key file format".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plaintext_round_trips_without_a_passphrase() {
+        let secret_bytes = Keypair::new().to_bytes().to_vec();
+
+        let encoded = encode_key_file(&secret_bytes, None);
+        let decoded = decode_key_file(&encoded, None).expect("plaintext key file should decode");
+
+        assert_eq!(decoded, secret_bytes);
+    }
+
+    #[test]
+    fn encrypted_round_trips_with_the_right_passphrase() {
+        let secret_bytes = Keypair::new().to_bytes().to_vec();
+
+        let encoded = encode_key_file(&secret_bytes, Some("correct horse battery staple"));
+        let decoded =
+            decode_key_file(&encoded, Some("correct horse battery staple")).expect("should decrypt with passphrase");
+
+        assert_eq!(decoded, secret_bytes);
+    }
+
+    #[test]
+    fn encrypted_rejects_the_wrong_passphrase() {
+        let secret_bytes = Keypair::new().to_bytes().to_vec();
+
+        let encoded = encode_key_file(&secret_bytes, Some("correct horse battery staple"));
+
+        assert!(decode_key_file(&encoded, Some("wrong passphrase")).is_err());
+    }
+
+    #[test]
+    fn encrypted_rejects_a_missing_passphrase() {
+        let secret_bytes = Keypair::new().to_bytes().to_vec();
+
+        let encoded = encode_key_file(&secret_bytes, Some("correct horse battery staple"));
+
+        assert!(decode_key_file(&encoded, None).is_err());
+    }
+}
@@ -4,14 +4,31 @@ use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     signature::{Keypair, Signer,Signature},
-    system_program,
-    system_instruction
+    transaction::Transaction,
+    message::Message,
 };
+#[allow(deprecated)]
+use solana_sdk::system_instruction;
 use spl_token::instruction::{initialize_mint,mint_to,transfer as spl_transfer};
 use base64::{engine::general_purpose, Engine as _};
 use base58::{ToBase58, FromBase58};
 use std::str::FromStr;
 
+mod rpc;
+mod ws;
+mod keystore;
+mod auth;
+mod http_sig;
+mod protect;
+mod ata;
+use rpc::{RpcClient, SubmitTransactionRequest, SubmitTransactionResponse};
+use ws::{SubscriptionRegistry, SubscriptionSession};
+use keystore::Keystore;
+use auth::OidcConfig;
+use protect::{AuthMode, RouteAuth};
+use ata::{create_associated_token_account_instruction, derive_associated_token_account};
+use std::sync::Arc;
+
 #[derive(Serialize)]
 struct SuccessResponse<T> {
     success: bool,
@@ -32,7 +49,8 @@ struct KeypairResponse {
 
 #[derive(Deserialize)]
 struct CreateTokenRequest {
-    mintAuthority: String,
+    #[serde(rename = "mintAuthority")]
+    mint_authority: String,
     mint: String,
     decimals: u8,
 }
@@ -70,30 +88,59 @@ struct InstructionResponse {
 
 
 #[derive(Deserialize)]
-struct SignMessageRequest {
+struct VerifyMessageRequest {
     message: String,
+    signature: String,
+    pubkey: String,
+}
+
+#[derive(Deserialize)]
+struct ImportKeyRequest {
     secret: String,
 }
 
 #[derive(Serialize)]
-struct SignMessageResponse {
-    signature: String,
-    public_key: String,
-    message: String,
+struct ImportKeyResponse {
+    pubkey: String,
+}
+
+#[derive(Serialize)]
+struct ListKeysResponse {
+    pubkeys: Vec<String>,
 }
 
 #[derive(Deserialize)]
-struct VerifyMessageRequest {
+struct RemoteSignRequest {
+    pubkey: String,
     message: String,
+}
+
+#[derive(Serialize)]
+struct RemoteSignResponse {
     signature: String,
+    public_key: String,
+}
+
+#[derive(Deserialize)]
+struct AirdropRequest {
     pubkey: String,
+    lamports: u64,
 }
 
 #[derive(Serialize)]
-struct VerifyMessageResponse {
-    valid: bool,
-    message: String,
-    pubkey: String,
+struct AirdropResponse {
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct BalanceResponse {
+    lamports: u64,
+    token_account: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct BalanceQuery {
+    token_account: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -109,6 +156,13 @@ struct SendTokenRequest {
     mint: String,
     owner: String,
     amount: u64,
+    /// When true, `owner`/`destination` are treated as wallet pubkeys and resolved to their
+    /// Associated Token Accounts instead of being used as token-account addresses directly.
+    #[serde(default)]
+    resolve_ata: bool,
+    /// Fee payer for the create-ATA instruction, if one is needed. Defaults to `owner`.
+    #[serde(default)]
+    payer: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -117,27 +171,47 @@ struct TokenAccountMeta {
     is_signer: bool,
 }
 
+#[derive(Deserialize)]
+struct DeriveAtaRequest {
+    owner: String,
+    mint: String,
+}
+
+#[derive(Serialize)]
+struct DeriveAtaResponse {
+    ata: String,
+}
+
+#[derive(Deserialize)]
+struct CreateAtaRequest {
+    payer: String,
+    owner: String,
+    mint: String,
+}
+
+#[derive(Serialize)]
+struct SendTokenInstructionsResponse {
+    instructions: Vec<InstructionResponse>,
+}
+
 
 #[post("/keypair")]
 async fn generate_keypair() -> impl Responder {
-    match Keypair::new() {
-        keypair => {
-            let pubkey = keypair.pubkey().to_string();
-            let secret = keypair.to_bytes().to_vec().to_base58();
-            let bytes= keypair.to_bytes();
-            print!("Generated Keypair: pubkey: {}, secret: {:?}", pubkey, bytes);
-
-            HttpResponse::Ok().json(SuccessResponse {
-                success: true,
-                data: KeypairResponse { pubkey, secret },
-            })
-        }
-    }
+    let keypair = Keypair::new();
+    let pubkey = keypair.pubkey().to_string();
+    let secret = keypair.to_bytes().to_vec().to_base58();
+    let bytes = keypair.to_bytes();
+    print!("Generated Keypair: pubkey: {}, secret: {:?}", pubkey, bytes);
+
+    HttpResponse::Ok().json(SuccessResponse {
+        success: true,
+        data: KeypairResponse { pubkey, secret },
+    })
 }
 
 #[post("/token/create")]
 async fn create_token(req: web::Json<CreateTokenRequest>) -> impl Responder {
-    let mintAuthority = match Pubkey::from_str(&req.mintAuthority) {
+    let mint_authority = match Pubkey::from_str(&req.mint_authority) {
         Ok(pk) => pk,
         Err(_) => {
             return HttpResponse::BadRequest().json(ErrorResponse {
@@ -157,13 +231,10 @@ async fn create_token(req: web::Json<CreateTokenRequest>) -> impl Responder {
         }
     };
 
-    // Use system_program::id() as rent_sysvar is deprecated in Solana v2.2+
-    let rent_sysvar = solana_sdk::sysvar::rent::id();
-
     let instr_result = initialize_mint(
         &spl_token::id(),
         &mint,
-        &mintAuthority,
+        &mint_authority,
         None,
         req.decimals,
     );
@@ -287,56 +358,11 @@ async fn mint_token(req: web::Json<MintTokenRequest>) -> impl Responder {
 //     Ok(base58_str)
 // }
 
-#[post("/message/sign")]
-async fn sign_message(req: web::Json<SignMessageRequest>) -> impl Responder {
-    if req.message.is_empty() || req.secret.is_empty() {
-        return HttpResponse::BadRequest().json(serde_json::json!({
-            "success": false,
-            "error": "Missing required fields"
-        }));
-    }
-
-    let secret_bytes = match req.secret.from_base58() {
-        Ok(b) => b,
-        Err(_) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "success": false,
-                "error": "Invalid base58 secret key"
-            }));
-        }
-    };
-
-    // let base64_secret = match base58_to_base64(&req.secret) {
-    //     Ok(b64) => b64,
-    //     Err(_) => {
-    //         return HttpResponse::BadRequest().json(serde_json::json!({
-    //             "success": false,
-    //             "error": "Failed to convert secret key to base64"
-    //         }));
-    //     }
-    // };
-
-    let keypair = match Keypair::from_bytes(&secret_bytes) {
-        Ok(kp) => kp,
-        Err(_) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "success": false,
-                "error": "Missing required fields"
-            }));
-        }
-    };
-
-    let signature = keypair.sign_message(req.message.as_bytes());
-
-    HttpResponse::Ok().json(serde_json::json!({
-        "success": true,
-        "data": {
-            "signature": general_purpose::STANDARD.encode(signature.as_ref()),
-            "public_key": keypair.pubkey().to_string(),
-            "message": req.message
-        }
-    }))
-}
+// `/message/sign` used to accept a raw base58 secret in the request body and sign with it
+// directly. That's exactly what the keystore series (chunk0-3) exists to stop doing - it's
+// been replaced by the keystore-backed `/sign` endpoint (see `remote_sign`), which takes a
+// pubkey instead of a private key. There is intentionally no secret-in-body signing route
+// left in this crate.
 
 #[post("/message/verify")]
 async fn verify_message(req: web::Json<VerifyMessageRequest>) -> impl Responder {
@@ -382,6 +408,117 @@ async fn verify_message(req: web::Json<VerifyMessageRequest>) -> impl Responder
     }))
 }
 
+#[post("/keys/import")]
+async fn import_key(req: web::Json<ImportKeyRequest>, keystore: web::Data<Keystore>) -> impl Responder {
+    match keystore.import(&req.secret).await {
+        Ok(pubkey) => HttpResponse::Ok().json(SuccessResponse {
+            success: true,
+            data: ImportKeyResponse { pubkey },
+        }),
+        Err(e) => HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: e.to_string(),
+        }),
+    }
+}
+
+#[actix_web::get("/keys")]
+async fn list_keys(keystore: web::Data<Keystore>) -> impl Responder {
+    HttpResponse::Ok().json(SuccessResponse {
+        success: true,
+        data: ListKeysResponse { pubkeys: keystore.list_pubkeys().await },
+    })
+}
+
+#[post("/sign")]
+async fn remote_sign(req: web::Json<RemoteSignRequest>, keystore: web::Data<Keystore>) -> impl Responder {
+    match keystore.sign(&req.pubkey, req.message.as_bytes()).await {
+        Some(signature) => HttpResponse::Ok().json(SuccessResponse {
+            success: true,
+            data: RemoteSignResponse {
+                signature: general_purpose::STANDARD.encode(signature.as_ref()),
+                public_key: req.pubkey.clone(),
+            },
+        }),
+        None => HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "No key loaded for the requested pubkey".to_string(),
+        }),
+    }
+}
+
+#[post("/airdrop")]
+async fn airdrop(req: web::Json<AirdropRequest>) -> impl Responder {
+    if Pubkey::from_str(&req.pubkey).is_err() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Invalid pubkey".to_string(),
+        });
+    }
+
+    let rpc = RpcClient::from_env();
+
+    if rpc.cluster().is_mainnet() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Airdrops are not permitted on mainnet".to_string(),
+        });
+    }
+
+    match rpc.request_airdrop(&req.pubkey, req.lamports).await {
+        Ok(signature) => HttpResponse::Ok().json(SuccessResponse {
+            success: true,
+            data: AirdropResponse { signature },
+        }),
+        Err(e) => HttpResponse::BadGateway().json(ErrorResponse {
+            success: false,
+            error: format!("Airdrop failed: {e}"),
+        }),
+    }
+}
+
+#[actix_web::get("/balance/{pubkey}")]
+async fn get_balance(path: web::Path<String>, query: web::Query<BalanceQuery>) -> impl Responder {
+    let pubkey = path.into_inner();
+
+    if Pubkey::from_str(&pubkey).is_err() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Invalid pubkey".to_string(),
+        });
+    }
+
+    let rpc = RpcClient::from_env();
+
+    let lamports = match rpc.get_balance(&pubkey).await {
+        Ok(lamports) => lamports,
+        Err(e) => {
+            return HttpResponse::BadGateway().json(ErrorResponse {
+                success: false,
+                error: format!("Failed to fetch balance: {e}"),
+            });
+        }
+    };
+
+    let token_account = match &query.token_account {
+        Some(account) => match rpc.get_token_account_balance(account).await {
+            Ok(balance) => Some(balance),
+            Err(e) => {
+                return HttpResponse::BadGateway().json(ErrorResponse {
+                    success: false,
+                    error: format!("Failed to fetch token account balance: {e}"),
+                });
+            }
+        },
+        None => None,
+    };
+
+    HttpResponse::Ok().json(SuccessResponse {
+        success: true,
+        data: BalanceResponse { lamports, token_account },
+    })
+}
+
 #[post("/send/sol")]
 async fn send_sol(req: web::Json<SendSolRequest>) -> impl Responder {
     let from = match Pubkey::from_str(&req.from) {
@@ -400,6 +537,7 @@ async fn send_sol(req: web::Json<SendSolRequest>) -> impl Responder {
         })),
     };
 
+    #[allow(deprecated)]
     let instr = system_instruction::transfer(&from, &to, req.lamports);
 
     HttpResponse::Ok().json(serde_json::json!({
@@ -415,6 +553,88 @@ async fn send_sol(req: web::Json<SendSolRequest>) -> impl Responder {
     }))
 }
 
+#[post("/ata/derive")]
+async fn derive_ata(req: web::Json<DeriveAtaRequest>) -> impl Responder {
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid owner pubkey".to_string(),
+            });
+        }
+    };
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid mint pubkey".to_string(),
+            });
+        }
+    };
+
+    HttpResponse::Ok().json(SuccessResponse {
+        success: true,
+        data: DeriveAtaResponse { ata: derive_associated_token_account(&owner, &mint).to_string() },
+    })
+}
+
+#[post("/ata/create")]
+async fn create_ata(req: web::Json<CreateAtaRequest>) -> impl Responder {
+    let payer = match Pubkey::from_str(&req.payer) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid payer pubkey".to_string(),
+            });
+        }
+    };
+
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid owner pubkey".to_string(),
+            });
+        }
+    };
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid mint pubkey".to_string(),
+            });
+        }
+    };
+
+    let instr = create_associated_token_account_instruction(&payer, &owner, &mint);
+
+    let accounts = instr
+        .accounts
+        .iter()
+        .map(|meta| AccountMetaResponse {
+            pubkey: meta.pubkey.to_string(),
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        })
+        .collect();
+
+    HttpResponse::Ok().json(SuccessResponse {
+        success: true,
+        data: InstructionResponse {
+            program_id: instr.program_id.to_string(),
+            accounts,
+            instruction_data: general_purpose::STANDARD.encode(instr.data),
+        },
+    })
+}
+
 #[post("/send/token")]
 async fn send_token(req: web::Json<SendTokenRequest>) -> impl Responder {
     let destination = match Pubkey::from_str(&req.destination) {
@@ -441,56 +661,354 @@ async fn send_token(req: web::Json<SendTokenRequest>) -> impl Responder {
         })),
     };
 
-    let instr = match spl_transfer(
-        &spl_token::id(),
-        &owner,         
-        &destination,  
-        &owner,         
-        &[],            
-        req.amount,
-    ) {
+    if !req.resolve_ata {
+        let instr = match spl_transfer(
+            &spl_token::id(),
+            &owner,
+            &destination,
+            &owner,
+            &[],
+            req.amount,
+        ) {
+            Ok(instr) => instr,
+            Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to build transfer: {}", e)
+            })),
+        };
+
+        let accounts = instr
+            .accounts
+            .iter()
+            .map(|meta| TokenAccountMeta {
+                pubkey: meta.pubkey.to_string(),
+                is_signer: meta.is_signer,
+            })
+            .collect::<Vec<_>>();
+
+        return HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "data": {
+                "program_id": instr.program_id.to_string(),
+                "accounts": accounts,
+                "instruction_data": general_purpose::STANDARD.encode(instr.data)
+            }
+        }));
+    }
+
+    let payer = match &req.payer {
+        Some(p) => match Pubkey::from_str(p) {
+            Ok(pk) => pk,
+            Err(_) => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    success: false,
+                    error: "Invalid payer address".to_string(),
+                });
+            }
+        },
+        None => owner,
+    };
+
+    let source_ata = derive_associated_token_account(&owner, &mint);
+    let dest_ata = derive_associated_token_account(&destination, &mint);
+
+    let rpc = RpcClient::from_env();
+    let dest_ata_exists = match rpc.get_account_info(&dest_ata.to_string()).await {
+        Ok(info) => !info["value"].is_null(),
+        Err(e) => {
+            return HttpResponse::BadGateway().json(ErrorResponse {
+                success: false,
+                error: format!("Failed to check destination ATA: {e}"),
+            });
+        }
+    };
+
+    let mut instructions = Vec::with_capacity(2);
+    if !dest_ata_exists {
+        let create_instr = create_associated_token_account_instruction(&payer, &destination, &mint);
+        instructions.push(InstructionResponse {
+            program_id: create_instr.program_id.to_string(),
+            accounts: create_instr
+                .accounts
+                .iter()
+                .map(|meta| AccountMetaResponse {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            instruction_data: general_purpose::STANDARD.encode(create_instr.data),
+        });
+    }
+
+    let transfer_instr = match spl_transfer(&spl_token::id(), &source_ata, &dest_ata, &owner, &[], req.amount) {
         Ok(instr) => instr,
-        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({
-            "success": false,
-            "error": format!("Failed to build transfer: {}", e)
-        })),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: format!("Failed to build transfer: {e}"),
+            });
+        }
     };
 
-    let accounts = instr
-        .accounts
-        .iter()
-        .map(|meta| TokenAccountMeta {
-            pubkey: meta.pubkey.to_string(),
-            is_signer: meta.is_signer,
-        })
-        .collect::<Vec<_>>();
+    instructions.push(InstructionResponse {
+        program_id: transfer_instr.program_id.to_string(),
+        accounts: transfer_instr
+            .accounts
+            .iter()
+            .map(|meta| AccountMetaResponse {
+                pubkey: meta.pubkey.to_string(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        instruction_data: general_purpose::STANDARD.encode(transfer_instr.data),
+    });
 
-    HttpResponse::Ok().json(serde_json::json!({
-        "success": true,
-        "data": {
-            "program_id": instr.program_id.to_string(),
-            "accounts": accounts,
-            "instruction_data": general_purpose::STANDARD.encode(instr.data)
+    HttpResponse::Ok().json(SuccessResponse {
+        success: true,
+        data: SendTokenInstructionsResponse { instructions },
+    })
+}
+
+
+#[post("/tx/submit")]
+async fn submit_transaction(req: web::Json<SubmitTransactionRequest>) -> impl Responder {
+    if req.instructions.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "At least one instruction is required".to_string(),
+        });
+    }
+
+    let mut instructions = Vec::with_capacity(req.instructions.len());
+    for instr in &req.instructions {
+        let program_id = match Pubkey::from_str(&instr.program_id) {
+            Ok(pk) => pk,
+            Err(_) => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    success: false,
+                    error: "Invalid program id in instructions".to_string(),
+                });
+            }
+        };
+
+        let mut accounts = Vec::with_capacity(instr.accounts.len());
+        for meta in &instr.accounts {
+            let pubkey = match Pubkey::from_str(&meta.pubkey) {
+                Ok(pk) => pk,
+                Err(_) => {
+                    return HttpResponse::BadRequest().json(ErrorResponse {
+                        success: false,
+                        error: "Invalid account pubkey in instructions".to_string(),
+                    });
+                }
+            };
+            accounts.push(AccountMeta {
+                pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            });
         }
-    }))
+
+        let data = match general_purpose::STANDARD.decode(&instr.instruction_data) {
+            Ok(d) => d,
+            Err(_) => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    success: false,
+                    error: "Invalid base64 instruction_data".to_string(),
+                });
+            }
+        };
+
+        instructions.push(Instruction {
+            program_id,
+            accounts,
+            data,
+        });
+    }
+
+    let mut signers = Vec::with_capacity(req.signers.len());
+    for secret in &req.signers {
+        let secret_bytes = match secret.from_base58() {
+            Ok(b) => b,
+            Err(_) => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    success: false,
+                    error: "Invalid base58 signer secret".to_string(),
+                });
+            }
+        };
+        let keypair = match Keypair::try_from(secret_bytes.as_slice()) {
+            Ok(kp) => kp,
+            Err(_) => {
+                return HttpResponse::BadRequest().json(ErrorResponse {
+                    success: false,
+                    error: "Invalid signer secret key".to_string(),
+                });
+            }
+        };
+        signers.push(keypair);
+    }
+
+    let rpc = RpcClient::from_env();
+
+    let blockhash_str = match rpc.get_latest_blockhash().await {
+        Ok(bh) => bh,
+        Err(e) => {
+            return HttpResponse::BadGateway().json(ErrorResponse {
+                success: false,
+                error: format!("Failed to fetch recent blockhash: {e}"),
+            });
+        }
+    };
+
+    let recent_blockhash = match solana_sdk::hash::Hash::from_str(&blockhash_str) {
+        Ok(h) => h,
+        Err(_) => {
+            return HttpResponse::BadGateway().json(ErrorResponse {
+                success: false,
+                error: "Cluster returned an invalid blockhash".to_string(),
+            });
+        }
+    };
+
+    let payer = signers.first().map(|kp| kp.pubkey());
+    let message = Message::new(&instructions, payer.as_ref());
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.message.recent_blockhash = recent_blockhash;
+
+    if !signers.is_empty() {
+        let signer_refs: Vec<&Keypair> = signers.iter().collect();
+        if let Err(e) = transaction.try_sign(&signer_refs, recent_blockhash) {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: format!("Failed to sign transaction: {e}"),
+            });
+        }
+    }
+
+    let tx_bytes = match bincode::serialize(&transaction) {
+        Ok(b) => b,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: format!("Failed to serialize transaction: {e}"),
+            });
+        }
+    };
+    let tx_base64 = general_purpose::STANDARD.encode(tx_bytes);
+
+    if req.simulate_only {
+        return match rpc.simulate_transaction(&tx_base64).await {
+            Ok(simulation) => HttpResponse::Ok().json(SuccessResponse {
+                success: true,
+                data: SubmitTransactionResponse {
+                    signature: None,
+                    simulated: true,
+                    simulation: Some(simulation),
+                },
+            }),
+            Err(e) => HttpResponse::BadGateway().json(ErrorResponse {
+                success: false,
+                error: format!("Simulation failed: {e}"),
+            }),
+        };
+    }
+
+    match rpc.send_transaction(&tx_base64).await {
+        Ok(signature) => HttpResponse::Ok().json(SuccessResponse {
+            success: true,
+            data: SubmitTransactionResponse {
+                signature: Some(signature),
+                simulated: false,
+                simulation: None,
+            },
+        }),
+        Err(e) => HttpResponse::BadGateway().json(ErrorResponse {
+            success: false,
+            error: format!("Failed to broadcast transaction: {e}"),
+        }),
+    }
 }
 
+async fn ws_subscribe(
+    req: actix_web::HttpRequest,
+    stream: web::Payload,
+    registry: web::Data<SubscriptionRegistry>,
+) -> Result<HttpResponse, actix_web::Error> {
+    actix_web_actors::ws::start(SubscriptionSession::new(registry.get_ref().clone()), &req, stream)
+}
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     println!(" Server running on http://127.0.0.1:8080");
 
-    HttpServer::new(|| {
+    let registry = SubscriptionRegistry::new();
+    registry.clone().spawn_poller(RpcClient::from_env(), std::time::Duration::from_secs(2));
+
+    let keystore_dir = std::env::var("KEYSTORE_DIR").unwrap_or_else(|_| "keystore".to_string());
+    let keystore_passphrase = std::env::var("KEYSTORE_PASSPHRASE").ok();
+    let keystore = web::Data::new(
+        Keystore::load(keystore_dir, keystore_passphrase)
+            .await
+            .expect("failed to load keystore"),
+    );
+
+    // AUTH_MODE selects how the protected scope below authenticates callers: "oidc" (bearer
+    // JWT, the default), "http-signature" (Ed25519-signed requests, no token server needed),
+    // or "none" to leave it open (only sensible for local development).
+    let auth_mode = std::env::var("AUTH_MODE").unwrap_or_else(|_| "oidc".to_string());
+
+    // Fail closed: if the operator asked for OIDC, a provider we can't reach or validate at
+    // startup must stop the server, not silently leave signing/minting/sending open.
+    let oidc_config = if auth_mode == "oidc" {
+        Some(Arc::new(
+            OidcConfig::from_env()
+                .await
+                .unwrap_or_else(|e| panic!("AUTH_MODE=oidc but OIDC setup failed: {e}")),
+        ))
+    } else {
+        None
+    };
+
+    if auth_mode != "oidc" && auth_mode != "http-signature" && auth_mode != "none" {
+        panic!("Unknown AUTH_MODE: {auth_mode} (expected \"oidc\", \"http-signature\", or \"none\")");
+    }
+
+    HttpServer::new(move || {
+        let mode = match auth_mode.as_str() {
+            "http-signature" => AuthMode::HttpSignature,
+            "none" => AuthMode::Disabled,
+            _ => AuthMode::Oidc(oidc_config.clone().expect("oidc_config set when AUTH_MODE=oidc")),
+        };
+
+        let protected = web::scope("")
+            .service(mint_token)
+            .service(import_key)
+            .service(remote_sign)
+            .service(send_sol)
+            .service(send_token)
+            .service(submit_transaction)
+            .wrap(RouteAuth(mode));
+
         App::new()
+            .app_data(web::Data::new(registry.clone()))
+            .app_data(keystore.clone())
             .service(generate_keypair)
             .service(create_token)
-            .service(mint_token)
-            .service(sign_message)
             .service(verify_message)
-            .service(send_sol)
-            .service(send_token)
+            .service(list_keys)
+            .service(airdrop)
+            .service(get_balance)
+            .service(derive_ata)
+            .service(create_ata)
+            // Must come before `protected`: that scope's empty ("") prefix matches every
+            // sub-path, so a route registered after it never gets a chance to match.
+            .route("/ws", web::get().to(ws_subscribe))
+            .service(protected)
     })
-    .bind(("0.0.0.0:8080"))?
+    .bind("0.0.0.0:8080")?
     .run()
     .await
 }
@@ -0,0 +1,130 @@
+//! Selects which of the two auth middlewares (or neither) guards the protected route
+//! scope, so `main` can pick a single concrete middleware type at startup regardless
+//! of which `AUTH_MODE` the operator configured.
+
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    web::Bytes,
+    Error, HttpMessage, HttpResponse,
+};
+use futures_util::future::LocalBoxFuture;
+use futures_util::StreamExt;
+
+use crate::auth::OidcConfig;
+use crate::http_sig::verify_signed_request;
+
+pub enum AuthMode {
+    Oidc(Arc<OidcConfig>),
+    HttpSignature,
+    Disabled,
+}
+
+pub struct RouteAuth(pub AuthMode);
+
+impl<S, B> Transform<S, ServiceRequest> for RouteAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RouteAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        let mode = match &self.0 {
+            AuthMode::Oidc(config) => AuthMode::Oidc(config.clone()),
+            AuthMode::HttpSignature => AuthMode::HttpSignature,
+            AuthMode::Disabled => AuthMode::Disabled,
+        };
+        ready(Ok(RouteAuthMiddleware { service: Rc::new(service), mode: Rc::new(mode) }))
+    }
+}
+
+pub struct RouteAuthMiddleware<S> {
+    service: Rc<S>,
+    mode: Rc<AuthMode>,
+}
+
+impl<S, B> Service<ServiceRequest> for RouteAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let mode = self.mode.clone();
+
+        Box::pin(async move {
+            match &*mode {
+                AuthMode::Disabled => service.call(req).await.map(|res| res.map_into_left_body()),
+
+                AuthMode::Oidc(config) => {
+                    let token = req
+                        .headers()
+                        .get("Authorization")
+                        .and_then(|h| h.to_str().ok())
+                        .and_then(|h| h.strip_prefix("Bearer "))
+                        .map(str::to_string);
+
+                    let result = match token {
+                        Some(token) => config.validate(&token),
+                        None => Err(crate::auth::OidcError("Missing bearer token".to_string())),
+                    };
+
+                    if let Err(e) = result {
+                        let response = HttpResponse::Unauthorized()
+                            .json(serde_json::json!({ "success": false, "error": e.to_string() }));
+                        return Ok(req.into_response(response).map_into_right_body());
+                    }
+
+                    service.call(req).await.map(|res| res.map_into_left_body())
+                }
+
+                AuthMode::HttpSignature => {
+                    let method = req.method().as_str().to_string();
+                    let path = req.uri().path_and_query().map(|pq| pq.as_str().to_string()).unwrap_or_default();
+
+                    let mut payload = req.take_payload();
+                    let mut body = Vec::new();
+                    while let Some(chunk) = payload.next().await {
+                        let Ok(chunk) = chunk else {
+                            return Ok(req.into_response(
+                                HttpResponse::BadRequest().json(
+                                    serde_json::json!({ "success": false, "error": "Failed to read request body" }),
+                                ),
+                            )
+                            .map_into_right_body());
+                        };
+                        body.extend_from_slice(&chunk);
+                    }
+                    req.set_payload(Payload::from(Bytes::from(body.clone())));
+
+                    if let Err(reason) = verify_signed_request(&method, &path, req.headers(), &body) {
+                        return Ok(req.into_response(
+                            HttpResponse::Unauthorized()
+                                .json(serde_json::json!({ "success": false, "error": reason })),
+                        )
+                        .map_into_right_body());
+                    }
+
+                    service.call(req).await.map(|res| res.map_into_left_body())
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,277 @@
+//! Thin wrapper around the Solana JSON-RPC interface.
+//!
+//! Everything else in this crate only *builds* instructions; this module is
+//! what actually talks to a cluster so they can be broadcast and observed.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Which cluster we're configured to talk to. Controls both the default RPC
+/// URL and whether cluster-only operations (like airdrops) are permitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cluster {
+    Devnet,
+    Mainnet,
+    Localnet,
+}
+
+impl Cluster {
+    fn default_url(self) -> &'static str {
+        match self {
+            Cluster::Devnet => "https://api.devnet.solana.com",
+            Cluster::Mainnet => "https://api.mainnet-beta.solana.com",
+            Cluster::Localnet => "http://127.0.0.1:8899",
+        }
+    }
+
+    fn from_env() -> Self {
+        match std::env::var("SOLANA_CLUSTER").as_deref() {
+            Ok("mainnet") | Ok("mainnet-beta") => Cluster::Mainnet,
+            Ok("localnet") | Ok("localhost") => Cluster::Localnet,
+            _ => Cluster::Devnet,
+        }
+    }
+
+    pub fn is_mainnet(self) -> bool {
+        matches!(self, Cluster::Mainnet)
+    }
+}
+
+/// Commitment level sent with RPC requests that accept one.
+#[derive(Debug, Clone, Copy)]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+
+    fn from_env() -> Self {
+        match std::env::var("SOLANA_COMMITMENT").as_deref() {
+            Ok("processed") => Commitment::Processed,
+            Ok("finalized") => Commitment::Finalized,
+            _ => Commitment::Confirmed,
+        }
+    }
+}
+
+/// Configuration for talking to a cluster, read once from the environment.
+///
+/// * `SOLANA_CLUSTER` - `devnet` (default), `mainnet`/`mainnet-beta`, or `localnet`/`localhost`
+/// * `SOLANA_RPC_URL` - overrides the cluster's default endpoint
+/// * `SOLANA_COMMITMENT` - `processed`, `confirmed` (default), or `finalized`
+#[derive(Debug, Clone)]
+pub struct RpcClient {
+    url: String,
+    commitment: Commitment,
+    cluster: Cluster,
+    http: reqwest::Client,
+}
+
+/// An error surfaced by a cluster RPC call, suitable for turning into an `ErrorResponse`.
+#[derive(Debug)]
+pub struct RpcError(pub String);
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse {
+    result: Option<Value>,
+    error: Option<JsonRpcErrorBody>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcErrorBody {
+    message: String,
+}
+
+impl RpcClient {
+    /// Builds a client from `SOLANA_CLUSTER` / `SOLANA_RPC_URL` / `SOLANA_COMMITMENT`.
+    pub fn from_env() -> Self {
+        let cluster = Cluster::from_env();
+        let url = std::env::var("SOLANA_RPC_URL").unwrap_or_else(|_| cluster.default_url().to_string());
+        RpcClient {
+            url,
+            commitment: Commitment::from_env(),
+            cluster,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn cluster(&self) -> Cluster {
+        self.cluster
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let resp = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| RpcError(format!("RPC request to {} failed: {e}", method)))?;
+
+        let parsed: JsonRpcResponse = resp
+            .json()
+            .await
+            .map_err(|e| RpcError(format!("Invalid RPC response for {}: {e}", method)))?;
+
+        if let Some(err) = parsed.error {
+            return Err(RpcError(err.message));
+        }
+
+        parsed
+            .result
+            .ok_or_else(|| RpcError(format!("RPC response for {} had no result", method)))
+    }
+
+    /// `getLatestBlockhash` - returns the base58 blockhash to stamp on a transaction before signing.
+    pub async fn get_latest_blockhash(&self) -> Result<String, RpcError> {
+        let result = self
+            .call(
+                "getLatestBlockhash",
+                json!([{ "commitment": self.commitment.as_str() }]),
+            )
+            .await?;
+
+        result["value"]["blockhash"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| RpcError("getLatestBlockhash response missing blockhash".to_string()))
+    }
+
+    /// `getAccountInfo` for `pubkey`, base64-encoded account data if it exists.
+    pub async fn get_account_info(&self, pubkey: &str) -> Result<Value, RpcError> {
+        self.call(
+            "getAccountInfo",
+            json!([pubkey, { "encoding": "base64", "commitment": self.commitment.as_str() }]),
+        )
+        .await
+    }
+
+    /// `getBalance` in lamports for `pubkey`.
+    pub async fn get_balance(&self, pubkey: &str) -> Result<u64, RpcError> {
+        let result = self
+            .call("getBalance", json!([pubkey, { "commitment": self.commitment.as_str() }]))
+            .await?;
+
+        result["value"]
+            .as_u64()
+            .ok_or_else(|| RpcError("getBalance response missing value".to_string()))
+    }
+
+    /// `getTokenAccountBalance` for an SPL token account.
+    pub async fn get_token_account_balance(&self, token_account: &str) -> Result<Value, RpcError> {
+        self.call("getTokenAccountBalance", json!([token_account])).await
+    }
+
+    /// `requestAirdrop` of `lamports` to `pubkey`. Only meaningful off mainnet; callers should
+    /// check `cluster().is_mainnet()` before calling this.
+    pub async fn request_airdrop(&self, pubkey: &str, lamports: u64) -> Result<String, RpcError> {
+        let result = self
+            .call(
+                "requestAirdrop",
+                json!([pubkey, lamports, { "commitment": self.commitment.as_str() }]),
+            )
+            .await?;
+
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| RpcError("requestAirdrop response missing signature".to_string()))
+    }
+
+    /// `simulateTransaction` - dry-run a base64-encoded, signed-or-unsigned transaction.
+    pub async fn simulate_transaction(&self, tx_base64: &str) -> Result<Value, RpcError> {
+        self.call(
+            "simulateTransaction",
+            json!([
+                tx_base64,
+                { "encoding": "base64", "commitment": self.commitment.as_str() }
+            ]),
+        )
+        .await
+    }
+
+    /// `sendTransaction` - broadcasts a base64-encoded, signed transaction and returns its signature.
+    pub async fn send_transaction(&self, tx_base64: &str) -> Result<String, RpcError> {
+        let result = self
+            .call(
+                "sendTransaction",
+                json!([
+                    tx_base64,
+                    { "encoding": "base64", "preflightCommitment": self.commitment.as_str() }
+                ]),
+            )
+            .await?;
+
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| RpcError("sendTransaction response missing signature".to_string()))
+    }
+
+    /// `getSignatureStatuses` for one or more transaction signatures.
+    pub async fn get_signature_statuses(&self, signatures: &[String]) -> Result<Value, RpcError> {
+        self.call(
+            "getSignatureStatuses",
+            json!([signatures, { "searchTransactionHistory": true }]),
+        )
+        .await
+    }
+}
+
+/// One instruction as produced by the existing `/token/*` and `/send/*` endpoints: a program id,
+/// its accounts, and base64-encoded instruction data.
+#[derive(Deserialize)]
+pub struct SubmittedInstruction {
+    pub program_id: String,
+    pub accounts: Vec<SubmittedAccountMeta>,
+    pub instruction_data: String,
+}
+
+#[derive(Deserialize)]
+pub struct SubmittedAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Deserialize)]
+pub struct SubmitTransactionRequest {
+    pub instructions: Vec<SubmittedInstruction>,
+    /// Base58 secret keys to sign the assembled transaction with, in signer order.
+    #[serde(default)]
+    pub signers: Vec<String>,
+    /// If true, run `simulateTransaction` and return without broadcasting.
+    #[serde(default)]
+    pub simulate_only: bool,
+}
+
+#[derive(Serialize)]
+pub struct SubmitTransactionResponse {
+    pub signature: Option<String>,
+    pub simulated: bool,
+    pub simulation: Option<Value>,
+}
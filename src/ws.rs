@@ -0,0 +1,247 @@
+//! WebSocket subscription service mirroring Solana's `signatureSubscribe` /
+//! `accountSubscribe` pubsub model.
+//!
+//! Clients open a socket at `/ws`, send a subscribe request, and receive a single push
+//! once the thing they're watching reaches the requested commitment - at which point
+//! they're auto-unsubscribed, same as the upstream cluster pubsub.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::{Actor, ActorContext, ActorFutureExt, AsyncContext, Handler, Message as ActixMessage, StreamHandler};
+use actix_web_actors::ws;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::rpc::RpcClient;
+
+pub type SubscriptionId = u64;
+
+#[derive(Deserialize)]
+#[serde(tag = "method", content = "params")]
+enum SubscribeRequest {
+    #[serde(rename = "signatureSubscribe")]
+    Signature((String,)),
+    #[serde(rename = "accountSubscribe")]
+    Account((String,)),
+}
+
+#[derive(Serialize)]
+struct SubscriptionAck {
+    subscription: SubscriptionId,
+}
+
+#[derive(Serialize)]
+struct SubscriptionNotification<T> {
+    subscription: SubscriptionId,
+    result: T,
+}
+
+/// Fans out a notification to exactly one subscriber, then the entry is dropped.
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+struct Notify(pub String);
+
+enum Watch {
+    Signature(String),
+    /// `baseline` is the account's data/lamports as observed on the poll tick right after
+    /// subscribing, filled in before any comparison happens. An already-existing account (the
+    /// common case - watching a token account for a balance change) must not fire on the very
+    /// next tick just because it exists; it must fire once that snapshot actually changes.
+    Account { pubkey: String, baseline: Option<Value> },
+}
+
+struct Subscriber {
+    watch: Watch,
+    addr: actix::Addr<SubscriptionSession>,
+}
+
+/// Process-wide table of live subscriptions, polled by a background task.
+#[derive(Clone)]
+pub struct SubscriptionRegistry {
+    next_id: Arc<AtomicU64>,
+    subscribers: Arc<RwLock<HashMap<SubscriptionId, Subscriber>>>,
+}
+
+impl SubscriptionRegistry {
+    pub fn new() -> Self {
+        SubscriptionRegistry {
+            next_id: Arc::new(AtomicU64::new(1)),
+            subscribers: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    async fn register(&self, watch: Watch, addr: actix::Addr<SubscriptionSession>) -> SubscriptionId {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        self.subscribers.write().await.insert(id, Subscriber { watch, addr });
+        id
+    }
+
+    async fn unregister(&self, id: SubscriptionId) {
+        self.subscribers.write().await.remove(&id);
+    }
+
+    /// Polls the RPC client once for every live subscription and notifies (then drops) any
+    /// that have resolved. Intended to be called on a fixed interval from a background task.
+    async fn poll_once(&self, rpc: &RpcClient) {
+        let snapshot: Vec<(SubscriptionId, String, actix::Addr<SubscriptionSession>)> = {
+            let subs = self.subscribers.read().await;
+            subs.iter()
+                .map(|(id, sub)| {
+                    let key = match &sub.watch {
+                        Watch::Signature(sig) => sig.clone(),
+                        Watch::Account { pubkey, .. } => pubkey.clone(),
+                    };
+                    (*id, key, sub.addr.clone())
+                })
+                .collect()
+        };
+
+        for (id, key, addr) in snapshot {
+            let is_account = {
+                let subs = self.subscribers.read().await;
+                matches!(subs.get(&id).map(|s| &s.watch), Some(Watch::Account { .. }))
+            };
+
+            let resolved = if is_account {
+                self.poll_account(id, &key, rpc).await
+            } else {
+                rpc.get_signature_statuses(std::slice::from_ref(&key))
+                    .await
+                    .ok()
+                    .filter(|v| !v["value"][0].is_null())
+            };
+
+            if let Some(result) = resolved {
+                let notification = SubscriptionNotification { subscription: id, result };
+                if let Ok(payload) = serde_json::to_string(&notification) {
+                    addr.do_send(Notify(payload));
+                }
+                self.unregister(id).await;
+            }
+        }
+    }
+
+    /// Fetches `pubkey`'s current account info and compares it against the baseline recorded
+    /// when the subscription was created. The first observation only fills in the baseline and
+    /// never resolves, since an already-existing account would otherwise fire on the very next
+    /// tick with nothing having actually changed. Returns the fresh value once it diverges.
+    async fn poll_account(&self, id: SubscriptionId, pubkey: &str, rpc: &RpcClient) -> Option<Value> {
+        let current = rpc.get_account_info(pubkey).await.ok()?;
+
+        let mut subs = self.subscribers.write().await;
+        let Watch::Account { baseline, .. } = &mut subs.get_mut(&id)?.watch else { return None };
+
+        match baseline {
+            None => {
+                *baseline = Some(current);
+                None
+            }
+            Some(baseline_value) if *baseline_value != current => Some(current),
+            _ => None,
+        }
+    }
+
+    /// Spawns the polling loop; call once from `main` alongside the HTTP server.
+    pub fn spawn_poller(self, rpc: RpcClient, interval: Duration) {
+        actix_web::rt::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.poll_once(&rpc).await;
+            }
+        });
+    }
+}
+
+/// One actor per open WebSocket connection. Supports at most one live subscription at a time -
+/// a second subscribe request is rejected rather than silently replacing `subscription_id`,
+/// which would otherwise leak the first subscription forever.
+pub struct SubscriptionSession {
+    registry: SubscriptionRegistry,
+    subscription_id: Option<SubscriptionId>,
+}
+
+impl SubscriptionSession {
+    pub fn new(registry: SubscriptionRegistry) -> Self {
+        SubscriptionSession { registry, subscription_id: None }
+    }
+}
+
+impl Actor for SubscriptionSession {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        if let Some(id) = self.subscription_id.take() {
+            let registry = self.registry.clone();
+            actix_web::rt::spawn(async move {
+                registry.unregister(id).await;
+            });
+        }
+    }
+}
+
+impl Handler<Notify> for SubscriptionSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: Notify, ctx: &mut Self::Context) {
+        ctx.text(msg.0);
+        self.subscription_id = None;
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for SubscriptionSession {
+    fn handle(&mut self, item: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        let msg = match item {
+            Ok(msg) => msg,
+            Err(_) => {
+                ctx.stop();
+                return;
+            }
+        };
+
+        match msg {
+            ws::Message::Ping(bytes) => ctx.pong(&bytes),
+            ws::Message::Text(text) => {
+                let request: SubscribeRequest = match serde_json::from_str(&text) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        ctx.text(format!(r#"{{"error":"invalid subscribe request: {e}"}}"#));
+                        return;
+                    }
+                };
+
+                // One connection supports a single live subscription at a time - a second
+                // subscribe here would silently overwrite `subscription_id` and leak the first
+                // one, since it's never unregistered.
+                if self.subscription_id.is_some() {
+                    ctx.text(r#"{"error":"connection already has a live subscription"}"#);
+                    return;
+                }
+
+                let watch = match request {
+                    SubscribeRequest::Signature((sig,)) => Watch::Signature(sig),
+                    SubscribeRequest::Account((pubkey,)) => Watch::Account { pubkey, baseline: None },
+                };
+
+                let registry = self.registry.clone();
+                let addr = ctx.address();
+                let fut = async move { registry.register(watch, addr).await };
+                ctx.wait(actix::fut::wrap_future(fut).map(|id, act: &mut Self, ctx| {
+                    act.subscription_id = Some(id);
+                    if let Ok(ack) = serde_json::to_string(&SubscriptionAck { subscription: id }) {
+                        ctx.text(ack);
+                    }
+                }));
+            }
+            ws::Message::Close(reason) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            _ => {}
+        }
+    }
+}